@@ -25,7 +25,12 @@
 //! );
 //! ```
 
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
 
 /// The Clean trait implements a `clean` method. It's recommended you use the provided [`clean`]
 /// function.
@@ -36,7 +41,107 @@ pub trait PathClean<T> {
 /// PathClean implemented for PathBuf
 impl PathClean<PathBuf> for PathBuf {
     fn clean(&self) -> PathBuf {
-        PathBuf::from(clean(self.to_str().unwrap_or("")))
+        self.as_path().clean()
+    }
+}
+
+/// PathClean implemented for Path. Unlike going through `to_str`, this goes
+/// through the raw bytes on Unix (where paths are arbitrary bytes, not
+/// necessarily valid UTF-8), so a path with invalid UTF-8 is cleaned
+/// correctly instead of being clobbered to `.`.
+impl PathClean<PathBuf> for Path {
+    fn clean(&self) -> PathBuf {
+        PathBuf::from(clean_osstr(self.as_os_str()))
+    }
+}
+
+/// PathClean implemented for `&Path`, so callers working with a borrowed
+/// path don't need to go through an owned `PathBuf` first.
+impl PathClean<PathBuf> for &Path {
+    fn clean(&self) -> PathBuf {
+        (*self).clean()
+    }
+}
+
+/// A `clean`-and-confine operation analogous to [`clean_jailed`], kept as a
+/// separate trait from [`PathClean`] so that implementing `PathClean` for
+/// your own type never requires also implementing this jail-specific,
+/// `&str`-flavored operation -- adding it as a required `PathClean` method
+/// would otherwise be a breaking change for every existing implementor.
+pub trait PathCleanJailed<T> {
+    /// See [`clean_jailed`].
+    fn clean_jailed(&self, root: &T) -> T;
+}
+
+/// PathCleanJailed implemented for PathBuf
+impl PathCleanJailed<PathBuf> for PathBuf {
+    fn clean_jailed(&self, root: &PathBuf) -> PathBuf {
+        self.as_path().clean_jailed(root)
+    }
+}
+
+/// PathCleanJailed implemented for Path. Like [`PathClean::clean`], this
+/// goes through the raw bytes on Unix so a `root`/path with invalid UTF-8
+/// is cleaned and confined correctly instead of being silently clobbered to
+/// an empty string (and thus, a vacuous jail).
+impl PathCleanJailed<PathBuf> for Path {
+    fn clean_jailed(&self, root: &PathBuf) -> PathBuf {
+        PathBuf::from(clean_jailed_osstr(root.as_os_str(), self.as_os_str()))
+    }
+}
+
+/// PathCleanJailed implemented for `&Path`, so callers working with a
+/// borrowed path don't need to go through an owned `PathBuf` first.
+impl PathCleanJailed<PathBuf> for &Path {
+    fn clean_jailed(&self, root: &PathBuf) -> PathBuf {
+        (*self).clean_jailed(root)
+    }
+}
+
+/// Cleans an `OsStr`, going through its raw bytes on Unix so non-UTF-8 paths
+/// are cleaned instead of lost. Platforms where `OsStr` isn't a thin wrapper
+/// over bytes (e.g. Windows, where it's WTF-8/UTF-16-ish) fall back to a
+/// UTF-8 round-trip, and leave `path` untouched (rather than lossily
+/// rewriting it) if that round-trip fails.
+fn clean_osstr(path: &OsStr) -> OsString {
+    #[cfg(unix)]
+    {
+        OsString::from_vec(clean_bytes(path.as_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        match path.to_str() {
+            Some(s) => OsString::from(clean(s)),
+            None => path.to_os_string(),
+        }
+    }
+}
+
+/// Cleans raw path bytes, which need not be valid UTF-8 (paths on Unix are
+/// arbitrary bytes). Only ascii `.`/`/` tokens are inspected; every other
+/// byte, valid UTF-8 or not, is copied through untouched.
+pub fn clean_bytes(path: &[u8]) -> Vec<u8> {
+    clean_internal(path).into_owned()
+}
+
+/// Cleans a `root`/`path` `OsStr` pair the way [`clean_jailed`] cleans a
+/// `root`/`path` string pair, going through raw bytes on Unix for the same
+/// non-UTF-8 safety reasons as [`clean_osstr`]. On platforms where that
+/// isn't possible and either `root` or `path` isn't valid UTF-8, this falls
+/// back to `root` itself -- since `clean_jailed` is a security boundary,
+/// the safe fallback is to confine to the jail rather than return an
+/// un-sanitized path.
+fn clean_jailed_osstr(root: &OsStr, path: &OsStr) -> OsString {
+    #[cfg(unix)]
+    {
+        OsString::from_vec(clean_jailed_bytes(root.as_bytes(), path.as_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        match (root.to_str(), path.to_str()) {
+            (Some(r), Some(p)) => OsString::from(clean_jailed(r, p)),
+            _ => root.to_os_string(),
+        }
     }
 }
 
@@ -49,19 +154,230 @@ impl PathClean<PathBuf> for PathBuf {
 ///
 /// If the result of this process is an empty string, return the string `"."`, representing the current directory.
 pub fn clean(path: &str) -> String {
-    let out = clean_internal(path.as_bytes());
-    // The code only matches/modifies ascii tokens and leaves the rest of
-    // the bytes as they are, so if the input string is valid utf8 the result
-    // will also be valid utf8.
+    clean_with(path, &CleanOptions::default())
+}
+
+/// Options controlling [`clean_with`]'s behavior. Constructed with
+/// [`CleanOptions::default`] and customized via its builder methods.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanOptions {
+    keep_dotdot: bool,
+    preserve_trailing_slash: bool,
+}
+
+impl CleanOptions {
+    /// `..` elements are folded away by default, same as [`clean`]. Pass
+    /// `true` to leave `..` elements untouched, only collapsing `.` and
+    /// duplicate slashes -- the same as LLVM's `remove_dots` without
+    /// `remove_dot_dot`. Useful for display-only normalization that
+    /// shouldn't change filesystem semantics.
+    pub fn keep_dotdot(mut self, keep: bool) -> Self {
+        self.keep_dotdot = keep;
+        self
+    }
+
+    /// Off by default. When on, a single trailing separator is re-appended
+    /// if `path` had one and the cleaned result isn't the bare root, so
+    /// callers that distinguish `foo` from `foo/` (e.g. as a directory
+    /// marker) don't lose that information.
+    pub fn preserve_trailing_slash(mut self, preserve: bool) -> Self {
+        self.preserve_trailing_slash = preserve;
+        self
+    }
+}
+
+/// Like [`clean`], but with behavior toggled by `options`. `clean(path)` is
+/// equivalent to `clean_with(path, &CleanOptions::default())`.
+pub fn clean_with(path: &str, options: &CleanOptions) -> String {
+    if *options == CleanOptions::default() {
+        // No options requested: take the zero-allocation fast path.
+        return clean_cow(path).into_owned();
+    }
+
+    let out = clean_internal_opts(path.as_bytes(), !options.keep_dotdot);
+    // Same reasoning as `clean_cow`: only ascii tokens are inspected/rewritten.
+    let mut out = unsafe { String::from_utf8_unchecked(out) };
+
+    if options.preserve_trailing_slash && path.ends_with('/') && out != "/" {
+        out.push('/');
+    }
+
+    out
+}
+
+/// Like [`clean`], but returns a borrowed [`Cow::Borrowed`] instead of
+/// allocating when `path` is already clean, which matters when cleaning many
+/// paths that are already canonical (e.g. in a hot loop).
+pub fn clean_cow(path: &str) -> Cow<'_, str> {
+    // Only ascii tokens are inspected/rewritten and every other byte is
+    // copied through as-is, so valid utf8 in implies valid utf8 out.
+    match clean_internal(path.as_bytes()) {
+        Cow::Borrowed(b) => Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(b) }),
+        Cow::Owned(v) => Cow::Owned(unsafe { String::from_utf8_unchecked(v) }),
+    }
+}
+
+/// Like [`clean`], but Windows-aware: `/` and `\` are treated as equivalent
+/// separators, a recognized volume prefix (`C:`, `C:\`) or UNC prefix
+/// (`\\server\share`, `\\?\...`) is preserved as an immutable root that `..`
+/// can never ascend past, and the output is normalized to use `\` as its
+/// separator.
+pub fn clean_windows(path: &str) -> String {
+    let out = clean_internal_windows(path.as_bytes());
+    // Same reasoning as `clean_cow`: only ascii tokens are inspected/rewritten.
     unsafe { String::from_utf8_unchecked(out) }
 }
 
-fn clean_internal(path: &[u8]) -> Vec<u8> {
+/// Cleans `path` the way an HTTP router would: the result is always rooted
+/// (a missing leading `/` is inserted), and an empty `path` cleans to `/`
+/// rather than `.`. Otherwise this folds `.`, `..`, and duplicate slashes
+/// the same as [`clean`], and preserves a trailing slash so callers can
+/// still distinguish `/foo` from `/foo/`.
+pub fn clean_url(path: &str) -> String {
+    clean_with(
+        &format!("/{path}"),
+        &CleanOptions::default().preserve_trailing_slash(true),
+    )
+}
+
+/// Cleans `path` and joins it to `root`, guaranteeing that the result can
+/// never lexically resolve outside `root` no matter how many `..` elements
+/// `path` starts with: any `..` that would ascend past `root` is clamped
+/// away rather than applied, the same way a normal rooted path clamps `..`
+/// at `/`. The clamp always succeeds -- the result is always `root` itself
+/// or a path lexically nested under it -- so there's no rejection path to
+/// report.
+///
+/// This is the sanitization step to run on an untrusted request path before
+/// joining it to a directory you're serving files from.
+///
+/// ```rust
+/// use path_clean::clean_jailed;
+/// assert_eq!(clean_jailed("/srv/www", "../.."), "/srv/www");
+/// assert_eq!(
+///     clean_jailed("/srv/www", "a/../../etc/passwd"),
+///     "/srv/www/etc/passwd"
+/// );
+/// ```
+pub fn clean_jailed(root: &str, path: &str) -> String {
+    // Same reasoning as `clean_cow`: only ascii tokens are inspected/rewritten,
+    // so valid utf8 in implies valid utf8 out.
+    unsafe { String::from_utf8_unchecked(clean_jailed_bytes(root.as_bytes(), path.as_bytes())) }
+}
+
+/// Byte-oriented core behind [`clean_jailed`]; see its docs for behavior.
+/// Operates on raw bytes, which need not be valid UTF-8, for the same
+/// reasons as [`clean_bytes`].
+pub fn clean_jailed_bytes(root: &[u8], path: &[u8]) -> Vec<u8> {
+    let root = clean_bytes(root);
+
+    // Clean `path` as if it were rooted at the jail: a leading separator in
+    // `path` carries no special meaning here (it's always relative to
+    // `root`), and any `..` that would ascend past that root is clamped
+    // away exactly like a normal rooted path clamps `..` at `/`.
+    let mut prefixed = Vec::with_capacity(path.len() + 1);
+    prefixed.push(b'/');
+    prefixed.extend_from_slice(path);
+    let mut jailed = clean_bytes(&prefixed);
+    jailed.remove(0);
+
+    let joined = if jailed.is_empty() || jailed == b"." {
+        root.clone()
+    } else if root == b"/" {
+        let mut joined = Vec::with_capacity(jailed.len() + 1);
+        joined.push(b'/');
+        joined.extend_from_slice(&jailed);
+        joined
+    } else {
+        let mut unclean = Vec::with_capacity(root.len() + 1 + jailed.len());
+        unclean.extend_from_slice(&root);
+        unclean.push(b'/');
+        unclean.extend_from_slice(&jailed);
+        clean_bytes(&unclean)
+    };
+
+    debug_assert!(
+        is_within_root(&joined, &root),
+        "clean_jailed produced a path outside its root"
+    );
+    joined
+}
+
+/// Whether `path` is `root` itself or lexically nested under it. `root` is
+/// assumed already clean; used as a `debug_assert!`-only sanity check on the
+/// guarantee behind [`clean_jailed_bytes`], since the `..`-clamping above
+/// makes this true by construction for every reachable `path`.
+fn is_within_root(path: &[u8], root: &[u8]) -> bool {
+    if root == b"." || root == b"/" {
+        // Everything `clean_jailed` can produce relative to "." or "/" is
+        // confined to it by construction -- there's no `..` left to escape.
+        return true;
+    }
+    path == root || (path.starts_with(root) && path.get(root.len()) == Some(&b'/'))
+}
+
+/// A lazily-allocating output buffer, mirroring the Go standard library's
+/// `lazybuf`: as long as what we'd write matches `s` byte-for-byte at the
+/// current position, no buffer is allocated and we just advance past it. The
+/// first time a write diverges from `s`, the `s[..w]` written so far is
+/// copied into an owned buffer and every append goes through that instead.
+struct LazyBuf<'a> {
+    s: &'a [u8],
+    buf: Option<Vec<u8>>,
+    w: usize,
+}
+
+impl<'a> LazyBuf<'a> {
+    fn new(s: &'a [u8]) -> Self {
+        LazyBuf { s, buf: None, w: 0 }
+    }
+
+    fn byte_at(&self, i: usize) -> u8 {
+        match &self.buf {
+            Some(buf) => buf[i],
+            None => self.s[i],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.w
+    }
+
+    fn truncate(&mut self, w: usize) {
+        self.w = w;
+        if let Some(buf) = &mut self.buf {
+            buf.truncate(w);
+        }
+    }
+
+    fn append(&mut self, c: u8) {
+        if self.buf.is_none() {
+            if self.w < self.s.len() && self.s[self.w] == c {
+                self.w += 1;
+                return;
+            }
+            let mut buf = Vec::with_capacity(self.s.len());
+            buf.extend_from_slice(&self.s[..self.w]);
+            self.buf = Some(buf);
+        }
+        self.buf.as_mut().unwrap().push(c);
+        self.w += 1;
+    }
+
+    fn into_cow(self) -> Cow<'a, [u8]> {
+        match self.buf {
+            Some(buf) => Cow::Owned(buf),
+            None => Cow::Borrowed(&self.s[..self.w]),
+        }
+    }
+}
+
+fn clean_internal(path: &[u8]) -> Cow<'_, [u8]> {
     static DOT: u8 = b'.';
     static SEP: u8 = b'/';
 
     if path.is_empty() {
-        return vec![DOT];
+        return Cow::Borrowed(b".");
     }
 
     let rooted = path[0] == SEP;
@@ -71,6 +387,166 @@ fn clean_internal(path: &[u8]) -> Vec<u8> {
     //  - reading from path; r is index of next byte to process.
     //  - dotdot is index in out where .. must stop, either because it is the
     //    leading slash or it is a leading ../../.. prefix.
+    let mut out = LazyBuf::new(path);
+    let mut r = 0;
+    let mut dotdot = 0;
+
+    if rooted {
+        out.append(SEP);
+        r = 1;
+        dotdot = 1;
+    }
+
+    while r < n {
+        if path[r] == SEP || path[r] == DOT && (r + 1 == n || path[r + 1] == SEP) {
+            // empty path element || . element: skip
+            r += 1;
+        } else if path[r] == DOT && path[r + 1] == DOT && (r + 2 == n || path[r + 2] == SEP) {
+            // .. element: remove to last separator
+            r += 2;
+            if out.len() > dotdot {
+                // can backtrack, truncate to last separator
+                let mut w = out.len() - 1;
+                while w > dotdot && out.byte_at(w) != SEP {
+                    w -= 1;
+                }
+                out.truncate(w);
+            } else if !rooted {
+                // cannot backtrack, but not rooted, so append .. element
+                if out.len() > 0 {
+                    out.append(SEP);
+                }
+                out.append(DOT);
+                out.append(DOT);
+                dotdot = out.len();
+            }
+        } else {
+            // real path element
+            // add slash if needed
+            if rooted && out.len() != 1 || !rooted && out.len() > 0 {
+                out.append(SEP);
+            }
+            while r < n && path[r] != SEP {
+                out.append(path[r]);
+                r += 1;
+            }
+        }
+    }
+
+    // Turn empty string into "."
+    if out.len() == 0 {
+        return Cow::Borrowed(b".");
+    }
+    out.into_cow()
+}
+
+fn clean_internal_windows(path: &[u8]) -> Vec<u8> {
+    if path.is_empty() {
+        return vec![b'.'];
+    }
+    let (prefix_len, rooted) = match windows_prefix_len(path) {
+        (0, _) => (0, is_win_sep(path[0])),
+        prefix => prefix,
+    };
+    clean_segments(path, b'\\', is_win_sep, prefix_len, rooted, true)
+}
+
+fn clean_internal_opts(path: &[u8], fold_dotdot: bool) -> Vec<u8> {
+    if path.is_empty() {
+        return vec![b'.'];
+    }
+    let rooted = path[0] == b'/';
+    clean_segments(path, b'/', |b| b == b'/', 0, rooted, fold_dotdot)
+}
+
+/// Whether a separator must be written to `out` before appending the next
+/// path element. None is needed at the very start of the output, nor right
+/// after a non-rooted prefix (e.g. the `C:` in `C:foo`, which attaches
+/// directly), nor when `out` already ends in a separator.
+fn needs_sep(out: &[u8], prefix_len: usize, rooted: bool, out_sep: u8) -> bool {
+    if out.is_empty() || (out.len() == prefix_len && !rooted) {
+        false
+    } else {
+        *out.last().unwrap() != out_sep
+    }
+}
+
+fn is_win_sep(b: u8) -> bool {
+    b == b'/' || b == b'\\'
+}
+
+/// Scans a Windows volume or UNC prefix at the start of `path`.
+///
+/// Returns the prefix length and whether it makes the path rooted, i.e. the
+/// boundary `..` can never back over. Recognizes `\\server\share` and
+/// `\\?\...` UNC prefixes, and `C:`/`C:\` drive prefixes (the latter only
+/// rooted if followed by a separator). Returns `(0, false)` if `path` has
+/// none of these.
+fn windows_prefix_len(path: &[u8]) -> (usize, bool) {
+    let n = path.len();
+
+    // `\\?\...` device prefix.
+    if n >= 4
+        && is_win_sep(path[0])
+        && is_win_sep(path[1])
+        && path[2] == b'?'
+        && is_win_sep(path[3])
+    {
+        return (4, true);
+    }
+
+    // `\\server\share` UNC prefix.
+    if n >= 2 && is_win_sep(path[0]) && is_win_sep(path[1]) {
+        let mut i = 2;
+        while i < n && !is_win_sep(path[i]) {
+            i += 1;
+        }
+        if i < n {
+            i += 1;
+            while i < n && !is_win_sep(path[i]) {
+                i += 1;
+            }
+        }
+        return (i, true);
+    }
+
+    // `C:` / `C:\` drive prefix.
+    if n >= 2 && path[0].is_ascii_alphabetic() && path[1] == b':' {
+        let mut i = 2;
+        let rooted = i < n && is_win_sep(path[i]);
+        if rooted {
+            i += 1;
+        }
+        return (i, rooted);
+    }
+
+    (0, false)
+}
+
+/// Shared byte-scanning implementation behind [`clean_internal_windows`] and
+/// [`clean_internal_opts`]. `out_sep` is the separator written into the
+/// output, `is_sep` recognizes separators in the input, `prefix_len` (copied
+/// verbatim into the output) and `rooted` describe any root already
+/// identified by the caller (a leading `/`, or a Windows volume/UNC prefix),
+/// and `fold_dotdot` controls whether `..` elements are resolved away (as in
+/// [`clean`]) or left untouched, like any other real path element.
+fn clean_segments(
+    path: &[u8],
+    out_sep: u8,
+    is_sep: fn(u8) -> bool,
+    prefix_len: usize,
+    rooted: bool,
+    fold_dotdot: bool,
+) -> Vec<u8> {
+    static DOT: u8 = b'.';
+
+    let n = path.len();
+
+    // Invariants:
+    //  - reading from path; r is index of next byte to process.
+    //  - dotdot is index in out where .. must stop, either because it is the
+    //    leading slash (or recognized prefix) or it is a leading ../../..
+    //    prefix.
     //
     // The go code this function is based on handles already-clean paths without
     // an allocation, but I haven't done that here because I think it
@@ -79,30 +555,42 @@ fn clean_internal(path: &[u8]) -> Vec<u8> {
     let mut r = 0;
     let mut dotdot = 0;
 
-    if rooted {
-        out.push(SEP);
+    if prefix_len > 0 {
+        // Copy verbatim, save for normalizing separators so the output is
+        // consistent even when e.g. a drive prefix was spelled `C:/`.
+        for &b in &path[..prefix_len] {
+            out.push(if is_sep(b) { out_sep } else { b });
+        }
+        r = prefix_len;
+        dotdot = prefix_len;
+    } else if rooted {
+        out.push(out_sep);
         r = 1;
-        dotdot = 1
+        dotdot = 1;
     }
 
     while r < n {
-        if path[r] == SEP || path[r] == DOT && (r + 1 == n || path[r + 1] == SEP) {
+        if is_sep(path[r]) || path[r] == DOT && (r + 1 == n || is_sep(path[r + 1])) {
             // empty path element || . element: skip
             r += 1;
-        } else if path[r] == DOT && path[r + 1] == DOT && (r + 2 == n || path[r + 2] == SEP) {
+        } else if fold_dotdot
+            && path[r] == DOT
+            && path[r + 1] == DOT
+            && (r + 2 == n || is_sep(path[r + 2]))
+        {
             // .. element: remove to last separator
             r += 2;
             if out.len() > dotdot {
                 // can backtrack, truncate to last separator
                 let mut w = out.len() - 1;
-                while w > dotdot && out[w] != SEP {
+                while w > dotdot && out[w] != out_sep {
                     w -= 1;
                 }
                 out.truncate(w);
             } else if !rooted {
                 // cannot backtrack, but not rooted, so append .. element
-                if !out.is_empty() {
-                    out.push(SEP);
+                if needs_sep(&out, prefix_len, rooted, out_sep) {
+                    out.push(out_sep);
                 }
                 out.push(DOT);
                 out.push(DOT);
@@ -110,11 +598,11 @@ fn clean_internal(path: &[u8]) -> Vec<u8> {
             }
         } else {
             // real path element
-            // add slash if needed
-            if rooted && out.len() != 1 || !rooted && !out.is_empty() {
-                out.push(SEP);
+            // add separator if needed
+            if needs_sep(&out, prefix_len, rooted, out_sep) {
+                out.push(out_sep);
             }
-            while r < n && path[r] != SEP {
+            while r < n && !is_sep(path[r]) {
                 out.push(path[r]);
                 r += 1;
             }
@@ -130,8 +618,12 @@ fn clean_internal(path: &[u8]) -> Vec<u8> {
 
 #[cfg(test)]
 mod tests {
-    use super::{clean, PathClean};
-    use std::path::PathBuf;
+    use super::{
+        clean, clean_bytes, clean_cow, clean_jailed, clean_url, clean_windows, clean_with,
+        CleanOptions, PathClean, PathCleanJailed,
+    };
+    use std::borrow::Cow;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test_empty_path_is_current_dir() {
@@ -218,4 +710,198 @@ mod tests {
             PathBuf::from("/path")
         );
     }
+
+    #[test]
+    fn test_clean_cow_borrows_already_clean_paths() {
+        let tests = vec!["/", ".", "..", "test/path", "/test/path"];
+
+        for test in tests {
+            assert!(matches!(clean_cow(test), Cow::Borrowed(_)));
+        }
+    }
+
+    #[test]
+    fn test_clean_cow_owns_when_path_changes() {
+        let tests = vec!["/test/../path/", "test//path", "./test"];
+
+        for test in tests {
+            assert!(matches!(clean_cow(test), Cow::Owned(_)));
+        }
+    }
+
+    #[test]
+    fn test_clean_jailed_cannot_escape_root() {
+        let tests = vec![
+            ("/srv/www", "../..", "/srv/www"),
+            ("/srv/www", "../../etc/passwd", "/srv/www/etc/passwd"),
+            ("/srv/www", "a/../../etc/passwd", "/srv/www/etc/passwd"),
+            ("/srv/www", "/../../x", "/srv/www/x"),
+            ("/srv/www", "..", "/srv/www"),
+            ("/srv/www", "a/b/../../../../c", "/srv/www/c"),
+            ("/srv/www", "a/b/..", "/srv/www/a"),
+            ("/", "../../x", "/x"),
+            (".", "../../x", "x"),
+        ];
+
+        for (root, path, want) in tests {
+            assert_eq!(clean_jailed(root, path), want);
+        }
+    }
+
+    #[test]
+    fn test_clean_jailed_trait_method() {
+        assert_eq!(
+            PathBuf::from("../..").clean_jailed(&PathBuf::from("/srv/www")),
+            PathBuf::from("/srv/www")
+        );
+    }
+
+    #[test]
+    fn test_clean_with_default_matches_clean() {
+        let tests = vec!["hello/world/..", "/test/../path/", "a/./b//c"];
+
+        for test in tests {
+            assert_eq!(clean_with(test, &CleanOptions::default()), clean(test));
+        }
+    }
+
+    #[test]
+    fn test_clean_with_keep_dotdot() {
+        let opts = CleanOptions::default().keep_dotdot(true);
+        let tests = vec![
+            ("a/../b", "a/../b"),
+            ("a//./b/../c", "a/b/../c"),
+            ("/a/../b", "/a/../b"),
+            ("./a", "a"),
+        ];
+
+        for (path, want) in tests {
+            assert_eq!(clean_with(path, &opts), want);
+        }
+    }
+
+    #[test]
+    fn test_clean_with_preserve_trailing_slash() {
+        let opts = CleanOptions::default().preserve_trailing_slash(true);
+        let tests = vec![
+            ("a/b/", "a/b/"),
+            ("a/b", "a/b"),
+            ("/", "/"),
+            ("a/b/..//", "a/"),
+        ];
+
+        for (path, want) in tests {
+            assert_eq!(clean_with(path, &opts), want);
+        }
+    }
+
+    #[test]
+    fn test_clean_url() {
+        let tests = vec![
+            ("", "/"),
+            ("foo/bar", "/foo/bar"),
+            ("/foo/bar", "/foo/bar"),
+            ("foo/bar/", "/foo/bar/"),
+            ("../foo", "/foo"),
+            ("foo/../../bar", "/bar"),
+            ("foo//./bar", "/foo/bar"),
+            (".", "/"),
+        ];
+
+        for (path, want) in tests {
+            assert_eq!(clean_url(path), want);
+        }
+    }
+
+    #[test]
+    fn test_clean_bytes() {
+        assert_eq!(clean_bytes(b"/test/../path/"), b"/path");
+        assert_eq!(clean_bytes(b""), b".");
+    }
+
+    #[test]
+    fn test_clean_jailed_bytes() {
+        use super::clean_jailed_bytes;
+
+        assert_eq!(
+            clean_jailed_bytes(b"/srv/www", b"../../etc/passwd"),
+            b"/srv/www/etc/passwd"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_jailed_preserves_non_utf8_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // "\xff/../secret" -- `\xff` is not valid UTF-8 on its own.
+        let invalid = OsStr::from_bytes(b"\xff/../secret");
+        let cleaned = Path::new(invalid).clean_jailed(&PathBuf::from("/srv/www"));
+        assert_eq!(cleaned.as_os_str().as_bytes(), b"/srv/www/secret");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_path_preserves_non_utf8_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // "foo/\xff/../bar" -- `\xff` is not valid UTF-8 on its own.
+        let invalid = OsStr::from_bytes(b"foo/\xff/../bar");
+        let cleaned = Path::new(invalid).clean();
+        assert_eq!(cleaned.as_os_str().as_bytes(), b"foo/bar");
+    }
+
+    #[test]
+    fn test_path_and_ref_path_clean() {
+        let expected = PathBuf::from("/path");
+        assert_eq!(Path::new("/test/../path/").clean(), expected);
+
+        let path: &Path = Path::new("/test/../path/");
+        assert_eq!(path.clean(), expected);
+    }
+
+    #[test]
+    fn test_clean_windows_backslashes() {
+        let tests = vec![
+            (r"C:\foo\..\bar", r"C:\bar"),
+            (r"C:/foo\bar", r"C:\foo\bar"),
+            (r"foo\.\bar", r"foo\bar"),
+            (r"foo\..\..\bar", r"..\bar"),
+            (r"\foo\..\bar", r"\bar"),
+        ];
+
+        for test in tests {
+            assert_eq!(clean_windows(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_clean_windows_drive_prefix() {
+        let tests = vec![
+            (r"C:\..", r"C:\"),
+            (r"C:\..\foo", r"C:\foo"),
+            (r"C:..\foo", r"C:..\foo"),
+            (r"C:foo\..\bar", r"C:bar"),
+        ];
+
+        for test in tests {
+            assert_eq!(clean_windows(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_clean_windows_unc_prefix() {
+        let tests = vec![
+            (r"\\server\share\a\..", r"\\server\share"),
+            (r"\\server\share\..\..", r"\\server\share"),
+            (r"\\server\share\a\..\b", r"\\server\share\b"),
+            (r"\\?\C:\foo\..\bar", r"\\?\C:\bar"),
+        ];
+
+        for test in tests {
+            assert_eq!(clean_windows(test.0), test.1);
+        }
+    }
 }